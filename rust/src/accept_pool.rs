@@ -0,0 +1,105 @@
+use std::{
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::Arc,
+    thread::JoinHandle,
+    time::Duration,
+};
+
+use crate::epoll::{Epoll, EventFlags, Token};
+
+const LISTENER_TOKEN: Token = Token(0);
+
+/// A worker pool that scales a single listening socket across threads without a thundering herd.
+///
+/// Each worker owns its own `Epoll` instance, and the shared listener is registered on every one
+/// of them with `EPOLLEXCLUSIVE | EPOLLIN`, so the kernel wakes only one (or a few) workers per
+/// incoming connection instead of all of them. Accepts are drained in a loop per wakeup since
+/// `EPOLLET` applies.
+pub struct AcceptPool {
+    workers: Vec<JoinHandle<std::io::Result<()>>>,
+}
+
+impl AcceptPool {
+    pub fn spawn(
+        listener: TcpListener,
+        worker_count: usize,
+        on_accept: impl Fn(TcpStream, SocketAddr) + Send + Sync + 'static,
+    ) -> std::io::Result<Self> {
+        listener.set_nonblocking(true)?;
+        let listener = Arc::new(listener);
+        let on_accept = Arc::new(on_accept);
+
+        let workers = (0..worker_count)
+            .map(|_| {
+                let listener = Arc::clone(&listener);
+                let on_accept = Arc::clone(&on_accept);
+                std::thread::spawn(move || Self::run(listener, on_accept.as_ref()))
+            })
+            .collect();
+
+        Ok(Self { workers })
+    }
+
+    fn run(listener: Arc<TcpListener>, on_accept: &(impl Fn(TcpStream, SocketAddr) + Send + Sync)) -> std::io::Result<()> {
+        let mut epoll = Epoll::create()?;
+        epoll.add(listener.as_ref(), LISTENER_TOKEN, EventFlags::EPOLLEXCLUSIVE | EventFlags::EPOLLIN | EventFlags::EPOLLET)?;
+
+        loop {
+            for event in epoll.wait(Duration::from_secs(60))? {
+                if event.token != LISTENER_TOKEN.0 {
+                    continue;
+                }
+                loop {
+                    match listener.accept() {
+                        Ok((stream, addr)) => on_accept(stream, addr),
+                        Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                        Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Blocks until every worker exits, surfacing the first error encountered (if any).
+    pub fn join(self) -> std::io::Result<()> {
+        let mut first_error = None;
+        for worker in self.workers {
+            if let Ok(Err(err)) = worker.join() {
+                first_error.get_or_insert(err);
+            }
+        }
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn workers_accept_connections_made_to_the_shared_listener() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        let accepted = Arc::new(Mutex::new(Vec::new()));
+        let accepted_worker = Arc::clone(&accepted);
+
+        let _pool = AcceptPool::spawn(listener, 2, move |_stream, peer_addr| {
+            accepted_worker.lock().unwrap().push(peer_addr);
+        }).unwrap();
+
+        let _client = TcpStream::connect(addr).unwrap();
+
+        let mut waited = Duration::ZERO;
+        while accepted.lock().unwrap().is_empty() && waited < Duration::from_secs(1) {
+            std::thread::sleep(Duration::from_millis(10));
+            waited += Duration::from_millis(10);
+        }
+
+        assert_eq!(accepted.lock().unwrap().len(), 1);
+    }
+}
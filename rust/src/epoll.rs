@@ -1,4 +1,8 @@
-use std::{os::fd::{AsRawFd, RawFd}, time::Duration};
+use std::{
+    collections::HashMap,
+    os::fd::{AsFd, AsRawFd, OwnedFd, RawFd},
+    time::Duration,
+};
 use bitflags::bitflags;
 
 #[allow(unused_macros)]
@@ -101,50 +105,185 @@ bitflags! {
 }
 
 
+/// An opaque, caller-chosen identifier attached to an interest when it is registered.
+///
+/// Unlike the raw fd, a `Token` is never interpreted by `Epoll` itself: it is handed back
+/// verbatim in the `Event` produced by `wait`, so callers can index directly into their own
+/// slab or state vector instead of looking the fd up in a side table.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Token(pub u64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub struct Event {
-    pub fd: RawFd,
+    pub token: u64,
     pub flags: EventFlags,
 }
 
+/// Number of interest slots `Epoll` allocates its event buffer with up front.
+const INITIAL_EVENTS_CAPACITY: usize = 1024;
+/// Ceiling the event buffer is allowed to grow to, doubling each time `wait` returns a full
+/// buffer (i.e. a burst that may have been truncated).
+const MAX_EVENTS_CAPACITY: usize = 65536;
+
+/// A borrowing iterator over the events produced by a `wait` call, yielded lazily from `Epoll`'s
+/// own buffer instead of being collected into a freshly allocated `Vec` on every call.
+pub struct Events<'a> {
+    inner: std::slice::Iter<'a, libc::epoll_event>,
+}
+
+impl Iterator for Events<'_> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        self.inner.next().map(|e| Event { token: e.u64, flags: EventFlags::from_bits_truncate(e.events) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A thin wrapper around `libc::sigset_t` for use with `Epoll::wait_with_sigmask`.
+pub struct SigSet(libc::sigset_t);
+
+impl SigSet {
+    /// An empty set, i.e. all signals blocked during the wait.
+    pub fn empty() -> Self {
+        let mut set = std::mem::MaybeUninit::uninit();
+        unsafe {
+            libc::sigfillset(set.as_mut_ptr());
+            Self(set.assume_init())
+        }
+    }
+
+    /// Leaves `signal` (e.g. `libc::SIGTERM`) unblocked for the duration of the wait.
+    pub fn add(&mut self, signal: i32) -> &mut Self {
+        unsafe { libc::sigdelset(&mut self.0, signal); }
+        self
+    }
+}
+
+/// The only flags `EPOLLEXCLUSIVE` may be combined with (besides itself); see the `epoll_ctl(2)`
+/// man page. `EPOLLHUP`/`EPOLLERR` are always reported regardless of what's requested, so they're
+/// harmless to also allow here.
+const EPOLLEXCLUSIVE_COMPATIBLE: EventFlags = EventFlags::EPOLLEXCLUSIVE
+    .union(EventFlags::EPOLLIN)
+    .union(EventFlags::EPOLLOUT)
+    .union(EventFlags::EPOLLWAKEUP)
+    .union(EventFlags::EPOLLET)
+    .union(EventFlags::EPOLLHUP)
+    .union(EventFlags::EPOLLERR);
+
 pub struct Epoll {
     epoll_fd: RawFd,
-    events: Vec<libc::epoll_event>
+    events: Vec<libc::epoll_event>,
+    /// Sources registered through `add_owned`, kept alive here for as long as they stay in the
+    /// interest list so a caller can't close one out from under the poller.
+    owned: HashMap<RawFd, OwnedFd>,
 }
 
 impl Epoll {
     pub fn create() -> std::io::Result<Self> {
-        let epoll_fd = syscall!(epoll_create(1))?;
+        let epoll_fd = syscall!(epoll_create1(libc::EPOLL_CLOEXEC))?;
         Ok(Self {
             epoll_fd: epoll_fd as RawFd,
-            events: Vec::with_capacity(1024)
+            events: vec![libc::epoll_event { events: 0, u64: 0 }; INITIAL_EVENTS_CAPACITY],
+            owned: HashMap::new(),
         })
     }
 
     #[inline]
-    pub fn add(&self, fd: &impl AsRawFd, flags: EventFlags) -> std::io::Result<()> {
-        let mut event = libc::epoll_event { events: flags.bits(), u64: fd.as_raw_fd() as u64 };
-        self._ctl(Ctl::Add, fd.as_raw_fd(), &mut event as *mut _)
+    pub fn add(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        if flags.contains(EventFlags::EPOLLEXCLUSIVE) && !EPOLLEXCLUSIVE_COMPATIBLE.contains(flags) {
+            return Err(std::io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        let raw = fd.as_fd().as_raw_fd();
+        let mut event = libc::epoll_event { events: flags.bits(), u64: token.0 };
+        self._ctl(Ctl::Add, raw, &mut event as *mut _)
+    }
+
+    /// Like `add`, but takes ownership of `fd` and keeps it alive in the interest map for as
+    /// long as it remains registered, closing it once `delete`d or when `self` is dropped.
+    pub fn add_owned(&mut self, fd: OwnedFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        let raw = fd.as_raw_fd();
+        self.add(&fd, token, flags)?;
+        self.owned.insert(raw, fd);
+        Ok(())
     }
 
     #[inline]
-    pub fn modify(&self, fd: &impl AsRawFd, flags: EventFlags) -> std::io::Result<()> {
-        let mut event = libc::epoll_event { events: flags.bits(), u64: fd.as_raw_fd() as u64 };
-        self._ctl(Ctl::Mod, fd.as_raw_fd(), &mut event as *mut _)
+    pub fn modify(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        // `EPOLLEXCLUSIVE` is only valid on `EPOLL_CTL_ADD`; a subsequent `EPOLL_CTL_MOD` on the
+        // same (epfd, fd) pair always fails, whether or not it re-specifies the flag.
+        if flags.contains(EventFlags::EPOLLEXCLUSIVE) {
+            return Err(std::io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        let raw = fd.as_fd().as_raw_fd();
+        let mut event = libc::epoll_event { events: flags.bits(), u64: token.0 };
+        self._ctl(Ctl::Mod, raw, &mut event as *mut _)
     }
 
     #[inline]
-    pub fn delete(&self, fd: &impl AsRawFd) -> std::io::Result<()> {
-        self._ctl(Ctl::Del, fd.as_raw_fd(), std::ptr::null_mut())
+    pub fn delete(&mut self, fd: &impl AsFd) -> std::io::Result<()> {
+        let raw = fd.as_fd().as_raw_fd();
+        self._ctl(Ctl::Del, raw, std::ptr::null_mut())?;
+        self.owned.remove(&raw);
+        Ok(())
+    }
+
+    /// Waits up to `timeout` for events, automatically retrying on `EINTR` (a signal interrupting
+    /// the underlying `epoll_wait`, common in real servers) against a monotonic deadline so a
+    /// storm of signals can't extend the wait past the caller's requested budget.
+    pub fn wait(&mut self, timeout: Duration) -> std::io::Result<Events<'_>> {
+        self.wait_impl(timeout, None)
+    }
+
+    /// Like `wait`, but atomically swaps the thread's signal mask for the duration of the call
+    /// via `epoll_pwait`. This lets a server block in the event loop with a signal (e.g.
+    /// `SIGTERM` for shutdown) blocked everywhere else and unblocked only at the wait point,
+    /// avoiding the classic self-pipe race between checking a "should stop" flag and blocking.
+    pub fn wait_with_sigmask(&mut self, timeout: Duration, sigmask: &SigSet) -> std::io::Result<Events<'_>> {
+        self.wait_impl(timeout, Some(sigmask))
     }
 
-    pub fn wait(&mut self, timeout: Duration) -> std::io::Result<Vec<Event>> {
-        self.events.clear();
-        self.events.resize(1024, libc::epoll_event { events: 0, u64: 0 });
-        let event_len = syscall!(epoll_wait(self.epoll_fd, self.events.as_mut_ptr(), self.events.len() as i32, timeout.as_millis() as i32))?;
-        Ok(self.events[0..event_len as usize]
-            .as_ref()
-            .iter()
-            .map(|e| Event{ fd: e.u64 as RawFd, flags: EventFlags::from_bits_truncate(e.events) }).collect())
+    fn wait_impl(&mut self, timeout: Duration, sigmask: Option<&SigSet>) -> std::io::Result<Events<'_>> {
+        let deadline = std::time::Instant::now() + timeout;
+        let event_len = loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            let timeout_ms = remaining.as_millis().min(i32::MAX as u128) as i32;
+
+            let result = match sigmask {
+                Some(sigmask) => syscall!(epoll_pwait(
+                    self.epoll_fd,
+                    self.events.as_mut_ptr(),
+                    self.events.len() as i32,
+                    timeout_ms,
+                    &sigmask.0 as *const _
+                )),
+                None => syscall!(epoll_wait(self.epoll_fd, self.events.as_mut_ptr(), self.events.len() as i32, timeout_ms)),
+            };
+
+            match result {
+                Ok(n) => break n as usize,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {
+                    if std::time::Instant::now() >= deadline {
+                        break 0;
+                    }
+                    continue;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+
+        // The buffer came back completely full, which means a burst may have been truncated:
+        // grow it (up to a cap) so the next `wait` has more room, mirroring how high-throughput
+        // reactors size `maxevents`.
+        if event_len == self.events.len() && self.events.len() < MAX_EVENTS_CAPACITY {
+            let new_len = (self.events.len() * 2).min(MAX_EVENTS_CAPACITY);
+            self.events.resize(new_len, libc::epoll_event { events: 0, u64: 0 });
+        }
+
+        Ok(Events { inner: self.events[0..event_len].iter() })
     }
 
     #[inline]
@@ -154,3 +293,9 @@ impl Epoll {
     }
 }
 
+impl Drop for Epoll {
+    fn drop(&mut self) {
+        let _ = syscall!(close(self.epoll_fd));
+    }
+}
+
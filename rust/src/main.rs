@@ -1,10 +1,48 @@
-use std::{collections::HashMap, io::Read, net::{SocketAddr, TcpListener, TcpStream}, os::fd::{AsRawFd, RawFd}, time::Duration};
+use std::{
+    io::{self, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    os::fd::{AsFd, AsRawFd, BorrowedFd},
+    time::Duration,
+};
 mod epoll;
-use epoll::{Epoll, EventFlags};
+mod poll;
+mod select;
+mod poller;
+mod reactor;
+mod timer;
+mod waker;
+mod accept_pool;
+use epoll::{EventFlags, Token};
+use poller::Poller;
+use reactor::Reactor;
+
+const LISTENER_TOKEN: Token = Token(0);
 
 struct ClientData {
-    pub stream: TcpStream,
-    pub addr: SocketAddr,
+    stream: TcpStream,
+    addr: SocketAddr,
+}
+
+impl Read for ClientData {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.read(buf)
+    }
+}
+
+impl Write for ClientData {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl AsFd for ClientData {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.stream.as_fd()
+    }
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -13,39 +51,27 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("Running server on 51717");
 
-    let mut epoll = Epoll::create()?;
+    let mut reactor = Reactor::<ClientData>::new(Poller::epoll()?);
+    reactor.listen(LISTENER_TOKEN, listener)?;
     println!("Created epoll instance");
-    epoll.add(&listener, EventFlags::EPOLLET | EventFlags:: EPOLLIN)?;
-    println!("Added server fd to epoll instance");
-
-    let mut clients: HashMap<RawFd, ClientData> = HashMap::new();
 
     loop {
-        for event in epoll.wait(Duration::from_secs(60))? {
-            if event.fd == listener.as_raw_fd() {
-                let (stream, addr) = listener.accept()?;
-                epoll.add(&stream, EventFlags::EPOLLIN | EventFlags::EPOLLET | EventFlags::EPOLLHUP | EventFlags::EPOLLRDHUP )?;
-                clients.insert(stream.as_raw_fd(), ClientData { stream, addr });
+        reactor.poll(
+            Duration::from_secs(60),
+            |stream, addr| {
+                stream.set_nonblocking(true).expect("set_nonblocking");
+                let token = Token(stream.as_raw_fd() as u64);
                 println!("Client connected {}:{}", addr.ip(), addr.port());
-                continue;
-            }
-
-            if event.flags.contains(EventFlags::EPOLLIN) {
-                let client = clients.get_mut(&event.fd).unwrap();
-                let mut buffer = vec![0u8; 1024];
-                client.stream.read(&mut buffer)?;
-                println!("Client {}:{}: {}", client.addr.ip(), client.addr.port(), String::from_utf8_lossy(&buffer));
-            }
-
-            if event.flags.contains(EventFlags::EPOLLHUP) || event.flags.contains(EventFlags::EPOLLRDHUP) {
-                println!("{}, {}", event.flags.contains(EventFlags::EPOLLHUP), event.flags.contains(EventFlags::EPOLLRDHUP));
-                let client = clients.get_mut(&event.fd).unwrap();
+                let interest = EventFlags::EPOLLIN | EventFlags::EPOLLET | EventFlags::EPOLLHUP | EventFlags::EPOLLRDHUP;
+                (token, ClientData { stream, addr }, interest)
+            },
+            |client, _token, data| {
+                println!("Client {}:{}: {}", client.addr.ip(), client.addr.port(), String::from_utf8_lossy(data));
+            },
+            |_client, _token| {},
+            |client, _token| {
                 println!("Client disconnected {}:{}", client.addr.ip(), client.addr.port());
-                epoll.delete(&event.fd)?;
-                clients.remove(&event.fd);
-            }
-        };
+            },
+        )?;
     }
-
-    Ok(())
-}
\ No newline at end of file
+}
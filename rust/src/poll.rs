@@ -0,0 +1,108 @@
+use std::{collections::HashMap, os::fd::{AsFd, AsRawFd, OwnedFd, RawFd}, time::Duration};
+
+use crate::epoll::{Event, EventFlags, Token};
+
+macro_rules! syscall {
+    ($fn: ident ( $($arg: expr),* $(,)* ) ) => {{
+        let res = unsafe { libc::$fn($($arg, )*) };
+        if res == -1 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(res)
+        }
+    }};
+}
+
+/// Edge-triggered-only flags that `poll(2)` has no way to honor: it is inherently level
+/// triggered, so these are rejected at registration time rather than silently downgraded.
+const UNSUPPORTED_FLAGS: EventFlags = EventFlags::EPOLLET
+    .union(EventFlags::EPOLLONESHOT)
+    .union(EventFlags::EPOLLEXCLUSIVE);
+
+/// A `poll(2)`-backed interest list, for platforms or situations where `epoll` isn't available.
+///
+/// Mirrors the interest list as a flat `Vec<libc::pollfd>` alongside the `Token` each entry was
+/// registered with, since `pollfd` has no spare field to stash caller data in the way
+/// `epoll_event.data` does.
+pub struct Poll {
+    fds: Vec<libc::pollfd>,
+    tokens: Vec<Token>,
+    /// Sources registered through `add_owned`, kept alive here for as long as they stay in the
+    /// interest list so a caller can't close one out from under the poller.
+    owned: HashMap<RawFd, OwnedFd>,
+}
+
+impl Poll {
+    pub fn new() -> Self {
+        Self { fds: Vec::new(), tokens: Vec::new(), owned: HashMap::new() }
+    }
+
+    pub fn add(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        let events = Self::to_poll_events(flags)?;
+        self.fds.push(libc::pollfd { fd: fd.as_fd().as_raw_fd(), events, revents: 0 });
+        self.tokens.push(token);
+        Ok(())
+    }
+
+    /// Like `add`, but takes ownership of `fd` and keeps it alive in the interest map for as
+    /// long as it remains registered, closing it once `delete`d or when `self` is dropped.
+    pub fn add_owned(&mut self, fd: OwnedFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        let raw = fd.as_raw_fd();
+        self.add(&fd, token, flags)?;
+        self.owned.insert(raw, fd);
+        Ok(())
+    }
+
+    pub fn modify(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        let events = Self::to_poll_events(flags)?;
+        let index = self.index_of(fd.as_fd().as_raw_fd())?;
+        self.fds[index].events = events;
+        self.tokens[index] = token;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, fd: &impl AsFd) -> std::io::Result<()> {
+        let raw = fd.as_fd().as_raw_fd();
+        let index = self.index_of(raw)?;
+        self.fds.swap_remove(index);
+        self.tokens.swap_remove(index);
+        self.owned.remove(&raw);
+        Ok(())
+    }
+
+    pub fn wait(&mut self, timeout: Duration) -> std::io::Result<Vec<Event>> {
+        syscall!(poll(self.fds.as_mut_ptr(), self.fds.len() as libc::nfds_t, timeout.as_millis() as i32))?;
+        Ok(self.fds.iter()
+            .zip(self.tokens.iter())
+            .filter(|(pfd, _)| pfd.revents != 0)
+            .map(|(pfd, token)| Event { token: token.0, flags: Self::from_poll_revents(pfd.revents) })
+            .collect())
+    }
+
+    fn index_of(&self, fd: RawFd) -> std::io::Result<usize> {
+        self.fds.iter()
+            .position(|pfd| pfd.fd == fd)
+            .ok_or_else(|| std::io::Error::from_raw_os_error(libc::ENOENT))
+    }
+
+    fn to_poll_events(flags: EventFlags) -> std::io::Result<libc::c_short> {
+        if flags.intersects(UNSUPPORTED_FLAGS) {
+            return Err(std::io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        let mut events = 0;
+        if flags.contains(EventFlags::EPOLLIN) { events |= libc::POLLIN; }
+        if flags.contains(EventFlags::EPOLLOUT) { events |= libc::POLLOUT; }
+        if flags.contains(EventFlags::EPOLLPRI) { events |= libc::POLLPRI; }
+        Ok(events as libc::c_short)
+    }
+
+    fn from_poll_revents(revents: libc::c_short) -> EventFlags {
+        let mut flags = EventFlags::empty();
+        if revents & libc::POLLIN != 0 { flags |= EventFlags::EPOLLIN; }
+        if revents & libc::POLLOUT != 0 { flags |= EventFlags::EPOLLOUT; }
+        if revents & libc::POLLPRI != 0 { flags |= EventFlags::EPOLLPRI; }
+        if revents & libc::POLLHUP != 0 { flags |= EventFlags::EPOLLHUP; }
+        if revents & libc::POLLERR != 0 { flags |= EventFlags::EPOLLERR; }
+        flags
+    }
+}
@@ -0,0 +1,93 @@
+use std::{os::fd::{AsFd, OwnedFd}, time::Duration};
+
+use crate::{
+    epoll::{Epoll, Event, EventFlags, SigSet, Token},
+    poll::Poll,
+    select::Select,
+};
+
+/// A runtime-selectable event backend.
+///
+/// `Epoll` is the preferred variant on Linux, but `Poll` and `Select` let the same server code
+/// run wherever `epoll` is unavailable (containers with a restricted syscall filter, non-Linux
+/// targets reached through a compat layer, and so on). All three variants share the `add` /
+/// `modify` / `delete` / `wait` surface, so callers pick a backend once at startup. The `Poll`
+/// and `Select` variants are level triggered only: they reject `EPOLLET`, `EPOLLONESHOT` and
+/// `EPOLLEXCLUSIVE` with `EINVAL`, and `wait_with_sigmask` works only on `Epoll`.
+pub enum Poller {
+    Epoll(Epoll),
+    Poll(Poll),
+    Select(Select),
+}
+
+impl Poller {
+    pub fn epoll() -> std::io::Result<Self> {
+        Ok(Self::Epoll(Epoll::create()?))
+    }
+
+    pub fn poll() -> Self {
+        Self::Poll(Poll::new())
+    }
+
+    pub fn select() -> Self {
+        Self::Select(Select::new())
+    }
+
+    pub fn add(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        match self {
+            Self::Epoll(epoll) => epoll.add(fd, token, flags),
+            Self::Poll(poll) => poll.add(fd, token, flags),
+            Self::Select(select) => select.add(fd, token, flags),
+        }
+    }
+
+    /// Like `add`, but transfers ownership of `fd` to the backend so it can't be closed out from
+    /// under the poller.
+    pub fn add_owned(&mut self, fd: OwnedFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        match self {
+            Self::Epoll(epoll) => epoll.add_owned(fd, token, flags),
+            Self::Poll(poll) => poll.add_owned(fd, token, flags),
+            Self::Select(select) => select.add_owned(fd, token, flags),
+        }
+    }
+
+    pub fn modify(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        match self {
+            Self::Epoll(epoll) => epoll.modify(fd, token, flags),
+            Self::Poll(poll) => poll.modify(fd, token, flags),
+            Self::Select(select) => select.modify(fd, token, flags),
+        }
+    }
+
+    pub fn delete(&mut self, fd: &impl AsFd) -> std::io::Result<()> {
+        match self {
+            Self::Epoll(epoll) => epoll.delete(fd),
+            Self::Poll(poll) => poll.delete(fd),
+            Self::Select(select) => select.delete(fd),
+        }
+    }
+
+    /// Waits for events and appends them into `events`, which is cleared first. Reuses `events`'
+    /// allocation across calls instead of handing back a freshly allocated `Vec` every time; the
+    /// `Epoll` backend's own buffer (see `epoll::Events`) still gets copied in here, since its
+    /// borrow can't be held across the caller's per-event handling, which typically needs `self`
+    /// mutably again (to `modify`/`delete` the source that raised the event).
+    pub fn wait(&mut self, timeout: Duration, events: &mut Vec<Event>) -> std::io::Result<()> {
+        events.clear();
+        match self {
+            Self::Epoll(epoll) => events.extend(epoll.wait(timeout)?),
+            Self::Poll(poll) => events.extend(poll.wait(timeout)?),
+            Self::Select(select) => events.extend(select.wait(timeout)?),
+        }
+        Ok(())
+    }
+
+    /// Like `wait`, but with the thread's signal mask swapped for the duration of the call.
+    /// Only the `Epoll` backend (`epoll_pwait`) supports this.
+    pub fn wait_with_sigmask(&mut self, timeout: Duration, sigmask: &SigSet) -> std::io::Result<Vec<Event>> {
+        match self {
+            Self::Epoll(epoll) => Ok(epoll.wait_with_sigmask(timeout, sigmask)?.collect()),
+            Self::Poll(_) | Self::Select(_) => Err(std::io::Error::from_raw_os_error(libc::ENOTSUP)),
+        }
+    }
+}
@@ -0,0 +1,176 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    time::Duration,
+};
+
+use crate::{
+    epoll::{Event, EventFlags, Token},
+    poller::Poller,
+};
+
+struct Connection<S> {
+    source: S,
+    /// Interest flags the connection was registered with, minus `EPOLLOUT` — the base set to
+    /// restore once the outbound backlog drains.
+    interest: EventFlags,
+    outbound: Vec<u8>,
+}
+
+/// A non-blocking I/O engine built on top of `Poller`.
+///
+/// Where `main`'s original loop read a socket exactly once per `EPOLLIN` edge — silently losing
+/// data, since edge-triggered mode won't re-notify until a fresh edge arrives — `Reactor` drains
+/// each readable connection in a loop until the kernel reports `EWOULDBLOCK`/`EAGAIN`. Writes are
+/// symmetric: a non-blocking `write` that can't fully drain is stashed in a per-connection
+/// backlog and `EPOLLOUT` is armed, then flushed (and the interest dropped back down) once the
+/// fd reports writable again.
+pub struct Reactor<S> {
+    poller: Poller,
+    connections: HashMap<u64, Connection<S>>,
+    listener: Option<(Token, TcpListener)>,
+    /// Reused across `poll` calls so draining events doesn't allocate a fresh `Vec` every
+    /// iteration of the event loop.
+    events: Vec<Event>,
+}
+
+impl<S: Read + Write + std::os::fd::AsFd> Reactor<S> {
+    pub fn new(poller: Poller) -> Self {
+        Self { poller, connections: HashMap::new(), listener: None, events: Vec::new() }
+    }
+
+    /// Registers the accept socket. Accept edges are drained in their own loop (same reasoning
+    /// as read edges) and handed to `poll`'s `on_accept` callback.
+    pub fn listen(&mut self, token: Token, listener: TcpListener) -> std::io::Result<()> {
+        self.poller.add(&listener, token, EventFlags::EPOLLIN | EventFlags::EPOLLET)?;
+        self.listener = Some((token, listener));
+        Ok(())
+    }
+
+    pub fn register(&mut self, token: Token, source: S, interest: EventFlags) -> std::io::Result<()> {
+        self.poller.add(&source, token, interest)?;
+        self.connections.insert(token.0, Connection { source, interest: interest - EventFlags::EPOLLOUT, outbound: Vec::new() });
+        Ok(())
+    }
+
+    /// Queues `data` for `token`, flushing as much of the (possibly already pending) backlog as
+    /// the fd will currently accept without blocking.
+    pub fn write(&mut self, token: Token, data: &[u8]) -> std::io::Result<()> {
+        let connection = self.connections.get_mut(&token.0)
+            .ok_or_else(|| std::io::Error::from_raw_os_error(libc::ENOENT))?;
+        connection.outbound.extend_from_slice(data);
+        self.flush(token)
+    }
+
+    fn flush(&mut self, token: Token) -> std::io::Result<()> {
+        let connection = self.connections.get_mut(&token.0)
+            .ok_or_else(|| std::io::Error::from_raw_os_error(libc::ENOENT))?;
+
+        let mut written = 0;
+        while written < connection.outbound.len() {
+            match connection.source.write(&connection.outbound[written..]) {
+                Ok(0) => break,
+                Ok(n) => written += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => break,
+                Err(e) => return Err(e),
+            }
+        }
+        connection.outbound.drain(0..written);
+
+        let drained = connection.outbound.is_empty();
+        let flags = if drained { connection.interest } else { connection.interest | EventFlags::EPOLLOUT };
+        self.poller.modify(&connection.source, token, flags)
+    }
+
+    /// Drives one iteration of the event loop, dispatching each accept/readable/writable/hangup
+    /// edge to the matching callback. `on_readable` is called once per successfully read chunk
+    /// (so a single edge that needs several `read`s to drain surfaces as several calls), not
+    /// once per event.
+    pub fn poll(
+        &mut self,
+        timeout: Duration,
+        mut on_accept: impl FnMut(TcpStream, SocketAddr) -> (Token, S, EventFlags),
+        mut on_readable: impl FnMut(&mut S, Token, &[u8]),
+        mut on_writable: impl FnMut(&mut S, Token),
+        mut on_hup: impl FnMut(S, Token),
+    ) -> std::io::Result<()> {
+        self.poller.wait(timeout, &mut self.events)?;
+        for i in 0..self.events.len() {
+            let event = self.events[i];
+            let token = Token(event.token);
+
+            if self.listener.as_ref().is_some_and(|(t, _)| *t == token) {
+                self.accept_loop(&mut on_accept)?;
+                continue;
+            }
+
+            if event.flags.contains(EventFlags::EPOLLIN) && self.drain_readable(token, &mut on_readable) {
+                self.remove_and_notify(token, &mut on_hup);
+                continue;
+            }
+
+            if event.flags.contains(EventFlags::EPOLLOUT) {
+                if self.flush(token).is_err() {
+                    self.remove_and_notify(token, &mut on_hup);
+                    continue;
+                }
+                if let Some(connection) = self.connections.get_mut(&token.0) {
+                    on_writable(&mut connection.source, token);
+                }
+            }
+
+            if event.flags.contains(EventFlags::EPOLLHUP) || event.flags.contains(EventFlags::EPOLLRDHUP) {
+                self.remove_and_notify(token, &mut on_hup);
+            }
+        }
+        Ok(())
+    }
+
+    /// Accepts until `EWOULDBLOCK`/`EAGAIN`, since the listener is registered edge-triggered. A
+    /// hard error (e.g. `EMFILE`/`ENFILE`) only aborts this one drain pass rather than the whole
+    /// event loop — the listener stays registered, so the next edge gets a fresh attempt once fds
+    /// free up.
+    fn accept_loop(&mut self, on_accept: &mut impl FnMut(TcpStream, SocketAddr) -> (Token, S, EventFlags)) -> std::io::Result<()> {
+        loop {
+            let (stream, addr) = match &self.listener {
+                Some((_, listener)) => match listener.accept() {
+                    Ok(pair) => pair,
+                    Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return Ok(()),
+                    Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                    Err(_) => return Ok(()),
+                },
+                None => return Ok(()),
+            };
+            let (token, source, interest) = on_accept(stream, addr);
+            self.register(token, source, interest)?;
+        }
+    }
+
+    /// Reads `token` until `EWOULDBLOCK`/`EAGAIN`. Returns `true` if the peer closed (`read`
+    /// returned `0`) or the connection otherwise errored and should be torn down.
+    fn drain_readable(&mut self, token: Token, on_readable: &mut impl FnMut(&mut S, Token, &[u8])) -> bool {
+        let mut buffer = [0u8; 4096];
+        loop {
+            let connection = match self.connections.get_mut(&token.0) {
+                Some(connection) => connection,
+                None => return false,
+            };
+            match connection.source.read(&mut buffer) {
+                Ok(0) => return true,
+                Ok(n) => on_readable(&mut connection.source, token, &buffer[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => return false,
+                Err(_) => return true,
+            }
+        }
+    }
+
+    fn remove_and_notify(&mut self, token: Token, on_hup: &mut impl FnMut(S, Token)) {
+        if let Some(connection) = self.connections.remove(&token.0) {
+            let _ = self.poller.delete(&connection.source);
+            on_hup(connection.source, token);
+        }
+    }
+}
@@ -0,0 +1,132 @@
+use std::{collections::HashMap, os::fd::{AsFd, AsRawFd, OwnedFd, RawFd}, time::Duration};
+
+use crate::epoll::{Event, EventFlags, Token};
+
+macro_rules! syscall {
+    ($fn: ident ( $($arg: expr),* $(,)* ) ) => {{
+        let res = unsafe { libc::$fn($($arg, )*) };
+        if res == -1 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(res)
+        }
+    }};
+}
+
+/// Edge-triggered-only flags that `select(2)` has no way to honor, same as the `poll(2)` backend.
+const UNSUPPORTED_FLAGS: EventFlags = EventFlags::EPOLLET
+    .union(EventFlags::EPOLLONESHOT)
+    .union(EventFlags::EPOLLEXCLUSIVE);
+
+/// Ceiling imposed by `fd_set`'s fixed-size bitmap (see `select(2)`, `FD_SETSIZE`). Registering
+/// a fd at or above this value is rejected rather than silently corrupting the bitmap.
+const FD_SETSIZE: RawFd = 1024;
+
+struct Interest {
+    fd: RawFd,
+    token: Token,
+    flags: EventFlags,
+}
+
+/// A `select(2)`-backed interest list, for the platforms or containers where neither `epoll` nor
+/// `poll` is an option. Rebuilds the read/write/except `fd_set`s from the interest list on every
+/// `wait`, since `select` consumes them in place.
+pub struct Select {
+    interests: Vec<Interest>,
+    /// Sources registered through `add_owned`, kept alive here for as long as they stay in the
+    /// interest list so a caller can't close one out from under the poller.
+    owned: HashMap<RawFd, OwnedFd>,
+}
+
+impl Select {
+    pub fn new() -> Self {
+        Self { interests: Vec::new(), owned: HashMap::new() }
+    }
+
+    pub fn add(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        let fd = fd.as_fd().as_raw_fd();
+        if flags.intersects(UNSUPPORTED_FLAGS) {
+            return Err(std::io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        if fd >= FD_SETSIZE {
+            return Err(std::io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        self.interests.push(Interest { fd, token, flags });
+        Ok(())
+    }
+
+    /// Like `add`, but takes ownership of `fd` and keeps it alive in the interest map for as
+    /// long as it remains registered, closing it once `delete`d or when `self` is dropped.
+    pub fn add_owned(&mut self, fd: OwnedFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        let raw = fd.as_raw_fd();
+        self.add(&fd, token, flags)?;
+        self.owned.insert(raw, fd);
+        Ok(())
+    }
+
+    pub fn modify(&mut self, fd: &impl AsFd, token: Token, flags: EventFlags) -> std::io::Result<()> {
+        if flags.intersects(UNSUPPORTED_FLAGS) {
+            return Err(std::io::Error::from_raw_os_error(libc::EINVAL));
+        }
+        let interest = self.find_mut(fd.as_fd().as_raw_fd())?;
+        interest.token = token;
+        interest.flags = flags;
+        Ok(())
+    }
+
+    pub fn delete(&mut self, fd: &impl AsFd) -> std::io::Result<()> {
+        let fd = fd.as_fd().as_raw_fd();
+        let index = self.interests.iter().position(|i| i.fd == fd)
+            .ok_or_else(|| std::io::Error::from_raw_os_error(libc::ENOENT))?;
+        self.interests.swap_remove(index);
+        self.owned.remove(&fd);
+        Ok(())
+    }
+
+    pub fn wait(&mut self, timeout: Duration) -> std::io::Result<Vec<Event>> {
+        let mut read_fds: libc::fd_set = unsafe { std::mem::zeroed() };
+        let mut write_fds: libc::fd_set = unsafe { std::mem::zeroed() };
+        let mut except_fds: libc::fd_set = unsafe { std::mem::zeroed() };
+        let mut max_fd: RawFd = -1;
+
+        unsafe {
+            libc::FD_ZERO(&mut read_fds);
+            libc::FD_ZERO(&mut write_fds);
+            libc::FD_ZERO(&mut except_fds);
+        }
+        for interest in &self.interests {
+            if interest.flags.contains(EventFlags::EPOLLIN) {
+                unsafe { libc::FD_SET(interest.fd, &mut read_fds); }
+            }
+            if interest.flags.contains(EventFlags::EPOLLOUT) {
+                unsafe { libc::FD_SET(interest.fd, &mut write_fds); }
+            }
+            if interest.flags.contains(EventFlags::EPOLLPRI) {
+                unsafe { libc::FD_SET(interest.fd, &mut except_fds); }
+            }
+            max_fd = max_fd.max(interest.fd);
+        }
+
+        let mut timeval = libc::timeval {
+            tv_sec: timeout.as_secs() as libc::time_t,
+            tv_usec: timeout.subsec_micros() as libc::suseconds_t,
+        };
+        syscall!(select(max_fd + 1, &mut read_fds, &mut write_fds, &mut except_fds, &mut timeval))?;
+
+        Ok(self.interests.iter()
+            .filter_map(|interest| {
+                let mut flags = EventFlags::empty();
+                if unsafe { libc::FD_ISSET(interest.fd, &read_fds) } { flags |= EventFlags::EPOLLIN; }
+                if unsafe { libc::FD_ISSET(interest.fd, &write_fds) } { flags |= EventFlags::EPOLLOUT; }
+                if unsafe { libc::FD_ISSET(interest.fd, &except_fds) } { flags |= EventFlags::EPOLLPRI; }
+                if flags.is_empty() { None } else { Some(Event { token: interest.token.0, flags }) }
+            })
+            .collect())
+    }
+
+    fn find_mut(&mut self, fd: RawFd) -> std::io::Result<&mut Interest> {
+        self.interests.iter_mut()
+            .find(|i| i.fd == fd)
+            .ok_or_else(|| std::io::Error::from_raw_os_error(libc::ENOENT))
+    }
+}
@@ -0,0 +1,91 @@
+use std::{
+    os::fd::{AsFd, AsRawFd, BorrowedFd, FromRawFd, OwnedFd},
+    time::Duration,
+};
+
+macro_rules! syscall {
+    ($fn: ident ( $($arg: expr),* $(,)* ) ) => {{
+        let res = unsafe { libc::$fn($($arg, )*) };
+        if res == -1 {
+            Err(std::io::Error::last_os_error())
+        } else {
+            Ok(res)
+        }
+    }};
+}
+
+/// A `timerfd`-backed source that reports as a normal `EPOLLIN` event, so a precise per-connection
+/// idle timeout (or any other scheduled wakeup) can sit in the same interest list as sockets
+/// instead of being approximated by `wait`'s single coarse `Duration` budget.
+pub struct Timer(OwnedFd);
+
+impl Timer {
+    /// Fires once, `delay` from now.
+    pub fn one_shot(delay: Duration) -> std::io::Result<Self> {
+        Self::create(delay, Duration::ZERO)
+    }
+
+    /// Fires every `interval`, starting one `interval` from now.
+    pub fn periodic(interval: Duration) -> std::io::Result<Self> {
+        Self::create(interval, interval)
+    }
+
+    fn create(initial: Duration, interval: Duration) -> std::io::Result<Self> {
+        let fd = syscall!(timerfd_create(libc::CLOCK_MONOTONIC, libc::TFD_CLOEXEC | libc::TFD_NONBLOCK))?;
+        let fd = unsafe { OwnedFd::from_raw_fd(fd) };
+
+        let spec = libc::itimerspec {
+            it_interval: to_timespec(interval),
+            it_value: to_timespec(initial),
+        };
+        syscall!(timerfd_settime(fd.as_raw_fd(), 0, &spec, std::ptr::null_mut()))?;
+
+        Ok(Self(fd))
+    }
+
+    /// Reads (and clears) the number of expirations since the last read. Call this after the
+    /// timer's `EPOLLIN` fires; returns `0` if called when no expiration is pending instead of
+    /// blocking, since the fd is non-blocking.
+    pub fn read_expirations(&self) -> std::io::Result<u64> {
+        let mut count: u64 = 0;
+        match syscall!(read(self.0.as_raw_fd(), &mut count as *mut u64 as *mut libc::c_void, 8)) {
+            Ok(_) => Ok(count),
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => Ok(0),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl AsFd for Timer {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+fn to_timespec(duration: Duration) -> libc::timespec {
+    libc::timespec {
+        tv_sec: duration.as_secs() as libc::time_t,
+        tv_nsec: duration.subsec_nanos() as libc::c_long,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_shot_fires_once_then_stays_quiet() {
+        let timer = Timer::one_shot(Duration::from_millis(10)).unwrap();
+        assert_eq!(timer.read_expirations().unwrap(), 0);
+        std::thread::sleep(Duration::from_millis(40));
+        assert_eq!(timer.read_expirations().unwrap(), 1);
+        assert_eq!(timer.read_expirations().unwrap(), 0);
+    }
+
+    #[test]
+    fn periodic_accumulates_expirations_between_reads() {
+        let timer = Timer::periodic(Duration::from_millis(10)).unwrap();
+        std::thread::sleep(Duration::from_millis(45));
+        assert!(timer.read_expirations().unwrap() >= 2);
+    }
+}